@@ -1,10 +1,16 @@
-use std::{cmp::Ordering, fmt::Display, fs, process};
-
-use anyhow::Result;
-use cargo_metadata::{Dependency, Metadata, MetadataCommand, Package};
+use std::{
+    cmp::Ordering,
+    collections::{HashMap, HashSet},
+    fmt::Display,
+    fs, process,
+};
+
+use anyhow::{Context, Result};
+use cargo_metadata::{Dependency, DependencyKind, Metadata, MetadataCommand, Package};
 use clap::{Args, Parser, Subcommand};
 use colored::Colorize;
 use nucleo_matcher::{Config, Matcher, pattern::Atom};
+use semver::VersionReq;
 use toml_edit::DocumentMut;
 
 #[derive(Parser)]
@@ -33,6 +39,18 @@ struct FeatureArgs {
     #[arg(short, long, value_name = "DEPENDENCY")]
     dependency: Option<String>,
 
+    /// Enable a feature (repeatable); non-interactive, requires --package and --dependency
+    #[arg(long = "enable", value_name = "FEATURE")]
+    enable: Vec<String>,
+
+    /// Disable a feature (repeatable); non-interactive, requires --package and --dependency
+    #[arg(long = "disable", value_name = "FEATURE")]
+    disable: Vec<String>,
+
+    /// Disable default features; non-interactive, requires --package and --dependency
+    #[arg(long, default_value_t = false)]
+    no_default_features: bool,
+
     #[command(subcommand)]
     command: Option<FeatureCommands>,
 }
@@ -41,6 +59,12 @@ struct FeatureArgs {
 enum FeatureCommands {
     /// List workspace dependencies
     List(FeatureListArgs),
+
+    /// Add a new dependency with interactive feature selection
+    Add(FeatureAddArgs),
+
+    /// Report the union of features requested for dependencies shared across members
+    Unify(FeatureUnifyArgs),
 }
 
 #[derive(Args)]
@@ -55,6 +79,60 @@ struct FeatureListArgs {
 
     #[arg(short, long, default_value_t = false)]
     all: bool,
+
+    /// Print the full transitive closure activated by each enabled feature
+    #[arg(short, long, default_value_t = false)]
+    transitive: bool,
+}
+
+#[derive(Args)]
+struct FeatureAddArgs {
+    /// Crate spec, e.g. `serde` or `serde@1.0`
+    crate_spec: String,
+
+    /// Workspace package to add the dependency to
+    #[arg(short, long, value_name = "PACKAGE")]
+    package: Option<String>,
+
+    /// Add the dependency from a git repository instead of a registry;
+    /// its features are not looked up, so no selection prompt is shown
+    #[arg(long, value_name = "URL")]
+    git: Option<String>,
+
+    /// Add the dependency from a local path instead of a registry
+    #[arg(long, value_name = "PATH")]
+    path: Option<String>,
+}
+
+#[derive(Args)]
+struct FeatureUnifyArgs {
+    /// Only report on a specific dependency crate
+    #[arg(short, long, value_name = "DEPENDENCY")]
+    dependency: Option<String>,
+}
+
+struct CrateSpec {
+    name: String,
+    version_req: Option<VersionReq>,
+}
+
+impl CrateSpec {
+    fn parse(spec: &str) -> Result<Self> {
+        match spec.split_once('@') {
+            Some((name, version)) => {
+                let version_req = VersionReq::parse(version)
+                    .with_context(|| format!("invalid version requirement `{version}`"))?;
+                Ok(CrateSpec {
+                    name: name.to_string(),
+                    version_req: Some(version_req),
+                })
+            }
+            None => Ok(CrateSpec {
+                name: spec.to_string(),
+                version_req: None,
+            }),
+        }
+    }
 }
 
 struct Feature {
@@ -127,6 +205,40 @@ fn get_dependency_features(metadata: &Metadata, dependency: &Dependency) -> Depe
     }
 }
 
+fn feature_includes_map(features: &[Feature]) -> HashMap<String, Vec<String>> {
+    features
+        .iter()
+        .map(|feature| (feature.name.clone(), feature.includes.clone()))
+        .collect()
+}
+
+fn transitive_feature_closure(
+    features_map: &HashMap<String, Vec<String>>,
+    feature: &str,
+) -> HashSet<String> {
+    let mut closure = HashSet::new();
+    let mut visited = HashSet::new();
+    let mut worklist = vec![feature.to_string()];
+
+    while let Some(current) = worklist.pop() {
+        if !visited.insert(current.clone()) {
+            continue;
+        }
+        let Some(includes) = features_map.get(&current) else {
+            continue;
+        };
+        for include in includes {
+            closure.insert(include.clone());
+            // dep:foo and crate/feature are terminal; only bare names recurse
+            if !include.contains('/') && !include.starts_with("dep:") {
+                worklist.push(include.clone());
+            }
+        }
+    }
+
+    closure
+}
+
 fn fuzzy_match<T: AsRef<str>>(items: impl IntoIterator<Item = T>, keyword: &str) -> Vec<(T, u16)> {
     let mut matcher = Matcher::new(Config::DEFAULT);
     let matches = Atom::new(
@@ -141,6 +253,56 @@ fn fuzzy_match<T: AsRef<str>>(items: impl IntoIterator<Item = T>, keyword: &str)
     matches
 }
 
+fn levenshtein_distance(a: &str, b: &str) -> usize {
+    let a = a.chars().collect::<Vec<_>>();
+    let b = b.chars().collect::<Vec<_>>();
+
+    let mut row = (0..=b.len()).collect::<Vec<_>>();
+    for (i, &ca) in a.iter().enumerate() {
+        let mut prev_diagonal = row[0];
+        row[0] = i + 1;
+        for (j, &cb) in b.iter().enumerate() {
+            let prev_above = row[j + 1];
+            row[j + 1] = if ca == cb {
+                prev_diagonal
+            } else {
+                1 + prev_diagonal.min(prev_above).min(row[j])
+            };
+            prev_diagonal = prev_above;
+        }
+    }
+
+    row[b.len()]
+}
+
+fn suggest_similar<'a>(keyword: &str, candidates: &[&'a str]) -> Vec<&'a str> {
+    let threshold = (keyword.len() / 3).max(3);
+    let mut suggestions = candidates
+        .iter()
+        .map(|candidate| (*candidate, levenshtein_distance(keyword, candidate)))
+        .filter(|(_, distance)| *distance <= threshold)
+        .collect::<Vec<_>>();
+    suggestions.sort_by_key(|(_, distance)| *distance);
+    suggestions.into_iter().map(|(candidate, _)| candidate).collect()
+}
+
+fn print_did_you_mean(kind: &str, keyword: &str, candidates: &[&str]) {
+    let suggestions = suggest_similar(keyword, candidates);
+    if suggestions.is_empty() {
+        eprintln!("{} no {kind} named '{keyword}'", "error:".red());
+    } else {
+        let suggestions = suggestions
+            .iter()
+            .map(|candidate| format!("'{candidate}'"))
+            .collect::<Vec<_>>()
+            .join(", ");
+        eprintln!(
+            "{} no {kind} named '{keyword}'\n  did you mean: {suggestions}",
+            "error:".red()
+        );
+    }
+}
+
 fn handle_prompt_result<T>(
     result: inquire::error::InquireResult<T>,
 ) -> inquire::error::InquireResult<T> {
@@ -166,11 +328,8 @@ fn choose_workspace_package(metadata: &Metadata, keyword: Option<String>) -> Res
         if let Some(keyword) = keyword {
             let matches = fuzzy_match(&select_package_options, &keyword);
             if matches.is_empty() {
-                let result =
-                    inquire::Select::new("Select workspace package:", select_package_options)
-                        .with_starting_filter_input(&keyword)
-                        .prompt();
-                handle_prompt_result(result)?
+                print_did_you_mean("workspace package", &keyword, &select_package_options);
+                anyhow::bail!("no workspace package named '{keyword}'");
             } else {
                 matches[0].0
             }
@@ -197,11 +356,8 @@ fn choose_dependency(package: &Package, keyword: Option<String>) -> Result<&Depe
         Some(keyword) => {
             let matches = fuzzy_match(&select_dependencies_options, &keyword);
             if matches.is_empty() {
-                let result =
-                    inquire::Select::new("Select dependency:", select_dependencies_options)
-                        .with_starting_filter_input(&keyword)
-                        .prompt();
-                handle_prompt_result(result)?
+                print_did_you_mean("dependency", &keyword, &select_dependencies_options);
+                anyhow::bail!("no dependency named '{keyword}'");
             } else {
                 matches[0].0
             }
@@ -248,7 +404,105 @@ fn choose_features(metadata: &Metadata, dependency: &Dependency) -> Result<Depen
     Ok(dependency_features)
 }
 
-fn manage_features(package: Option<String>, dependency: Option<String>) -> Result<()> {
+fn write_dependency_entry(
+    doc: &mut DocumentMut,
+    dependency_name: &str,
+    version: Option<&str>,
+    source: Option<(&str, &str)>,
+    uses_default_features: bool,
+    features: &[&String],
+) {
+    let mut array = toml_edit::Array::default();
+    features.iter().for_each(|f| {
+        array.push(f.as_str());
+    });
+
+    if source.is_none() && uses_default_features && array.is_empty() {
+        if let Some(version) = version {
+            doc["dependencies"][dependency_name] = toml_edit::value(version);
+            return;
+        }
+    }
+
+    doc["dependencies"][dependency_name] = toml_edit::value(toml_edit::InlineTable::new());
+    if let Some(version) = version {
+        doc["dependencies"][dependency_name]["version"] = toml_edit::value(version);
+    }
+    if let Some((key, value)) = source {
+        doc["dependencies"][dependency_name][key] = toml_edit::value(value);
+    }
+    if !uses_default_features {
+        doc["dependencies"][dependency_name]["default-features"] = toml_edit::value(false);
+    }
+    if !array.is_empty() {
+        doc["dependencies"][dependency_name]["features"] = toml_edit::value(array);
+    }
+}
+
+fn apply_feature_operations(
+    metadata: &Metadata,
+    dependency: &Dependency,
+    enable: &[String],
+    disable: &[String],
+    no_default_features: bool,
+) -> Result<DependencyFeatures> {
+    let dependency_features = get_dependency_features(metadata, dependency);
+    apply_feature_ops(dependency_features, enable, disable, no_default_features)
+}
+
+fn apply_feature_ops(
+    mut dependency_features: DependencyFeatures,
+    enable: &[String],
+    disable: &[String],
+    no_default_features: bool,
+) -> Result<DependencyFeatures> {
+    let known_features = dependency_features
+        .features
+        .iter()
+        .map(|feature| feature.name.as_str())
+        .collect::<Vec<_>>();
+
+    for feature in enable.iter().chain(disable.iter()) {
+        if feature != "default" && !known_features.contains(&feature.as_str()) {
+            print_did_you_mean("feature", feature, &known_features);
+            anyhow::bail!("no feature named '{feature}'");
+        }
+    }
+
+    for feature in enable {
+        if !dependency_features.enabled_features.contains(feature) {
+            dependency_features.enabled_features.push(feature.clone());
+        }
+    }
+
+    dependency_features
+        .enabled_features
+        .retain(|feature| !disable.contains(feature));
+
+    if no_default_features {
+        dependency_features
+            .enabled_features
+            .retain(|feature| feature != "default");
+    }
+
+    Ok(dependency_features)
+}
+
+fn manage_features(
+    package: Option<String>,
+    dependency: Option<String>,
+    enable: Vec<String>,
+    disable: Vec<String>,
+    no_default_features: bool,
+) -> Result<()> {
+    let has_explicit_ops = !enable.is_empty() || !disable.is_empty() || no_default_features;
+
+    if has_explicit_ops && (package.is_none() || dependency.is_none()) {
+        anyhow::bail!(
+            "--enable/--disable/--no-default-features require --package and --dependency"
+        );
+    }
+
     let metadata = MetadataCommand::new().exec()?;
 
     let workspace_package = choose_workspace_package(&metadata, package)?;
@@ -256,44 +510,182 @@ fn manage_features(package: Option<String>, dependency: Option<String>) -> Resul
     let dependency = choose_dependency(workspace_package, dependency)?;
     let dependency_name = dependency.name.as_str();
 
-    let dependency_features = choose_features(&metadata, dependency)?;
+    let dependency_features = if has_explicit_ops {
+        apply_feature_operations(&metadata, dependency, &enable, &disable, no_default_features)?
+    } else {
+        choose_features(&metadata, dependency)?
+    };
     let uses_default_features = dependency_features.uses_default_features();
+    let enabled_features = dependency_features.enabled_features_except_default();
 
     let toml_path = &workspace_package.manifest_path;
     let toml_plaintext = fs::read_to_string(toml_path)?;
     let mut doc = toml_plaintext.parse::<DocumentMut>()?;
 
-    let mut array = toml_edit::Array::default();
-    dependency_features
-        .enabled_features_except_default()
-        .into_iter()
-        .for_each(|f| {
-            array.push(f);
-        });
-
     let version = dependency
         .req
         .to_string()
         .trim_start_matches('^')
         .to_owned();
 
-    if uses_default_features && array.is_empty() {
-        // only declar version
-        doc["dependencies"][dependency_name] = toml_edit::value(version);
+    write_dependency_entry(
+        &mut doc,
+        dependency_name,
+        Some(&version),
+        None,
+        uses_default_features,
+        &enabled_features,
+    );
+
+    fs::write(toml_path, doc.to_string())?;
+
+    Ok(())
+}
+
+fn fetch_registry_features(
+    name: &str,
+    version_req: Option<&VersionReq>,
+) -> Result<(String, Vec<Feature>)> {
+    let client = reqwest::blocking::Client::builder()
+        .user_agent(concat!("cargo-extensions/", env!("CARGO_PKG_VERSION")))
+        .build()?;
+
+    let info: serde_json::Value = client
+        .get(format!("https://crates.io/api/v1/crates/{name}"))
+        .send()?
+        .error_for_status()
+        .with_context(|| format!("no crate named `{name}` found on crates.io"))?
+        .json()?;
+
+    let versions = info["versions"]
+        .as_array()
+        .context("malformed crates.io response: missing `versions`")?;
+
+    let matched = versions
+        .iter()
+        .find(|v| {
+            if v["yanked"].as_bool().unwrap_or(false) {
+                return false;
+            }
+            let num = v["num"].as_str().unwrap_or_default();
+            semver::Version::parse(num)
+                .map(|parsed| version_req.map_or(true, |req| req.matches(&parsed)))
+                .unwrap_or(false)
+        })
+        .with_context(|| format!("no unyanked version of `{name}` matches the requested version"))?;
+
+    let version = matched["num"].as_str().unwrap_or_default().to_string();
+    let features = matched["features"]
+        .as_object()
+        .map(|features| {
+            features
+                .iter()
+                .map(|(name, includes)| Feature {
+                    name: name.clone(),
+                    includes: includes
+                        .as_array()
+                        .map(|includes| {
+                            includes
+                                .iter()
+                                .filter_map(|x| x.as_str().map(str::to_string))
+                                .collect()
+                        })
+                        .unwrap_or_default(),
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    Ok((version, features))
+}
+
+fn add_dependency(args: FeatureAddArgs) -> Result<()> {
+    let metadata = MetadataCommand::new().exec()?;
+    let workspace_package = choose_workspace_package(&metadata, args.package)?;
+
+    let spec = CrateSpec::parse(&args.crate_spec)?;
+
+    let (version, source, features) = if let Some(path) = &args.path {
+        let manifest_path = std::path::Path::new(path).join("Cargo.toml");
+        let manifest = fs::read_to_string(&manifest_path)
+            .with_context(|| format!("failed to read {}", manifest_path.display()))?
+            .parse::<DocumentMut>()?;
+        let features = manifest["features"]
+            .as_table_like()
+            .map(|table| {
+                table
+                    .iter()
+                    .map(|(name, includes)| Feature {
+                        name: name.to_string(),
+                        includes: includes
+                            .as_array()
+                            .map(|includes| {
+                                includes
+                                    .iter()
+                                    .filter_map(|x| x.as_str().map(str::to_string))
+                                    .collect()
+                            })
+                            .unwrap_or_default(),
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        (None, Some(("path", path.as_str())), features)
+    } else if let Some(git) = &args.git {
+        (None, Some(("git", git.as_str())), Vec::new())
     } else {
-        // use inline table
-        doc["dependencies"][dependency_name] = toml_edit::value(toml_edit::InlineTable::new());
-        // set version
-        doc["dependencies"][dependency_name]["version"] = toml_edit::value(version);
-        if !uses_default_features {
-            // set default-features
-            doc["dependencies"][dependency_name]["default-features"] = toml_edit::value(false);
-        }
-        if !array.is_empty() {
-            // set features
-            doc["dependencies"][dependency_name]["features"] = toml_edit::value(array);
+        let (version, features) =
+            fetch_registry_features(&spec.name, spec.version_req.as_ref())?;
+        (Some(version), None, features)
+    };
+
+    let mut all_features = features;
+    all_features.sort_by(|a, b| {
+        if a.name.eq("default") {
+            Ordering::Less
+        } else {
+            a.name.cmp(&b.name)
         }
-    }
+    });
+
+    let toggle_features_options = all_features.iter().collect::<Vec<_>>();
+    let default_index = toggle_features_options
+        .iter()
+        .enumerate()
+        .filter(|(_i, x)| x.name.eq("default"))
+        .map(|(i, _x)| i)
+        .collect::<Vec<usize>>();
+
+    let enabled_features = if toggle_features_options.is_empty() {
+        Vec::new()
+    } else {
+        let result = inquire::MultiSelect::new("Toggle features", toggle_features_options)
+            .with_default(&default_index)
+            .prompt();
+        handle_prompt_result(result)?
+            .into_iter()
+            .map(|x| x.name.clone())
+            .collect::<Vec<String>>()
+    };
+
+    let uses_default_features = enabled_features.contains(&"default".to_string());
+    let enabled_features_except_default = enabled_features
+        .iter()
+        .filter(|x| x.ne(&"default"))
+        .collect::<Vec<_>>();
+
+    let toml_path = &workspace_package.manifest_path;
+    let toml_plaintext = fs::read_to_string(toml_path)?;
+    let mut doc = toml_plaintext.parse::<DocumentMut>()?;
+
+    write_dependency_entry(
+        &mut doc,
+        &spec.name,
+        version.as_deref(),
+        source,
+        uses_default_features,
+        &enabled_features_except_default,
+    );
 
     fs::write(toml_path, doc.to_string())?;
 
@@ -324,7 +716,37 @@ fn list_workspace_features(args: FeatureListArgs) -> Result<()> {
 
             println!("{:>2}{}:", "", dependency.name);
             let dependency_features = get_dependency_features(&metadata, dependency);
-            if args.all {
+            if args.transitive {
+                let features_map = feature_includes_map(&dependency_features.features);
+                let mut transitively_enabled = HashSet::new();
+
+                for feature in &dependency_features.enabled_features {
+                    let closure = transitive_feature_closure(&features_map, feature);
+                    let mut closure_list = closure.iter().cloned().collect::<Vec<_>>();
+                    closure_list.sort();
+                    println!(
+                        "{:>4}{} {}",
+                        "",
+                        feature.blue(),
+                        format!("=> [{}]", closure_list.join(", ")).bright_black()
+                    );
+                    transitively_enabled.extend(closure);
+                }
+
+                for feature in &dependency_features.enabled_features {
+                    transitively_enabled.remove(feature);
+                }
+                if !transitively_enabled.is_empty() {
+                    let mut transitive_list = transitively_enabled.into_iter().collect::<Vec<_>>();
+                    transitive_list.sort();
+                    println!(
+                        "{:>4}{}",
+                        "",
+                        format!("(also enabled transitively: {})", transitive_list.join(", "))
+                            .yellow()
+                    );
+                }
+            } else if args.all {
                 for feature in dependency_features.features {
                     let enabled = dependency_features.enabled_features.contains(&feature.name);
                     println!(
@@ -361,6 +783,79 @@ fn list_workspace_features(args: FeatureListArgs) -> Result<()> {
     Ok(())
 }
 
+fn unify_workspace_features(args: FeatureUnifyArgs) -> Result<()> {
+    let metadata = MetadataCommand::new().exec()?;
+    let workspace_packages = metadata.workspace_packages();
+
+    let mut by_crate: HashMap<&str, Vec<(&str, DependencyFeatures)>> = HashMap::new();
+    for package in &workspace_packages {
+        for dependency in &package.dependencies {
+            if dependency.kind != DependencyKind::Normal {
+                continue;
+            }
+            if args
+                .dependency
+                .as_ref()
+                .map_or(false, |x| !x.contains(&dependency.name))
+            {
+                continue;
+            }
+            let dependency_features = get_dependency_features(&metadata, dependency);
+            by_crate
+                .entry(dependency.name.as_str())
+                .or_default()
+                .push((package.name.as_str(), dependency_features));
+        }
+    }
+
+    let mut crate_names = by_crate.keys().copied().collect::<Vec<_>>();
+    crate_names.sort();
+
+    for crate_name in crate_names {
+        let members = &by_crate[crate_name];
+        if members.len() < 2 {
+            continue;
+        }
+
+        let unified = members
+            .iter()
+            .flat_map(|(_, features)| features.enabled_features.iter().cloned())
+            .collect::<HashSet<_>>();
+        let mut unified_list = unified.iter().cloned().collect::<Vec<_>>();
+        unified_list.sort();
+
+        println!(
+            "{}: {}",
+            crate_name.cyan(),
+            format!("unified = [{}]", unified_list.join(", ")).bright_black()
+        );
+
+        for (member_name, features) in members {
+            let requested = features
+                .enabled_features
+                .iter()
+                .cloned()
+                .collect::<HashSet<_>>();
+            let mut requested_list = requested.iter().cloned().collect::<Vec<_>>();
+            requested_list.sort();
+
+            println!("{:>2}{}: [{}]", "", member_name, requested_list.join(", "));
+
+            let mut extra = unified.difference(&requested).cloned().collect::<Vec<_>>();
+            if !extra.is_empty() {
+                extra.sort();
+                println!(
+                    "{:>4}{}",
+                    "",
+                    format!("also receives via unification: [{}]", extra.join(", ")).yellow()
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
+
 fn main() -> Result<()> {
     let cli = Cli::parse();
 
@@ -370,12 +865,145 @@ fn main() -> Result<()> {
                 FeatureCommands::List(list_args) => {
                     list_workspace_features(list_args)?;
                 }
+                FeatureCommands::Add(add_args) => {
+                    add_dependency(add_args)?;
+                }
+                FeatureCommands::Unify(unify_args) => {
+                    unify_workspace_features(unify_args)?;
+                }
             }
             return Ok(());
         } else {
-            manage_features(args.package, args.dependency)?;
+            manage_features(
+                args.package,
+                args.dependency,
+                args.enable,
+                args.disable,
+                args.no_default_features,
+            )?;
         }
     }
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn feature_map(pairs: &[(&str, &[&str])]) -> HashMap<String, Vec<String>> {
+        pairs
+            .iter()
+            .map(|(name, includes)| {
+                (
+                    name.to_string(),
+                    includes.iter().map(|x| x.to_string()).collect(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn transitive_closure_recurses_bare_names_and_stops_at_dep_and_crate_forms() {
+        let features_map = feature_map(&[
+            ("default", &["std"]),
+            ("std", &["dep:libc", "alloc"]),
+            ("alloc", &["other/full"]),
+        ]);
+
+        let closure = transitive_feature_closure(&features_map, "default");
+
+        assert_eq!(
+            closure,
+            HashSet::from([
+                "std".to_string(),
+                "dep:libc".to_string(),
+                "alloc".to_string(),
+                "other/full".to_string(),
+            ])
+        );
+    }
+
+    #[test]
+    fn transitive_closure_terminates_on_cycles() {
+        let features_map = feature_map(&[("a", &["b"]), ("b", &["a"])]);
+
+        let closure = transitive_feature_closure(&features_map, "a");
+
+        assert_eq!(closure, HashSet::from(["a".to_string(), "b".to_string()]));
+    }
+
+    #[test]
+    fn levenshtein_distance_matches_known_values() {
+        assert_eq!(levenshtein_distance("kitten", "sitting"), 3);
+        assert_eq!(levenshtein_distance("same", "same"), 0);
+        assert_eq!(levenshtein_distance("", "abc"), 3);
+    }
+
+    #[test]
+    fn suggest_similar_keeps_close_matches_and_drops_far_ones() {
+        let candidates = ["serde", "serde_json", "syn", "anyhow"];
+
+        let suggestions = suggest_similar("srede", &candidates);
+
+        assert_eq!(suggestions.first(), Some(&"serde"));
+        assert!(!suggestions.contains(&"anyhow"));
+    }
+
+    fn sample_dependency_features() -> DependencyFeatures {
+        DependencyFeatures {
+            features: vec![
+                Feature {
+                    name: "default".to_string(),
+                    includes: vec!["std".to_string()],
+                },
+                Feature {
+                    name: "std".to_string(),
+                    includes: vec![],
+                },
+                Feature {
+                    name: "alloc".to_string(),
+                    includes: vec![],
+                },
+            ],
+            enabled_features: vec!["default".to_string()],
+        }
+    }
+
+    #[test]
+    fn apply_feature_ops_enables_and_disables() {
+        let mut dependency_features = sample_dependency_features();
+        dependency_features.enabled_features.push("std".to_string());
+
+        let result = apply_feature_ops(
+            dependency_features,
+            &["alloc".to_string()],
+            &["std".to_string()],
+            false,
+        )
+        .unwrap();
+
+        assert!(result.enabled_features.contains(&"alloc".to_string()));
+        assert!(result.enabled_features.contains(&"default".to_string()));
+        assert!(!result.enabled_features.contains(&"std".to_string()));
+    }
+
+    #[test]
+    fn apply_feature_ops_no_default_features_wins_over_default() {
+        let result = apply_feature_ops(sample_dependency_features(), &[], &[], true).unwrap();
+
+        assert!(!result.enabled_features.contains(&"default".to_string()));
+    }
+
+    #[test]
+    fn apply_feature_ops_rejects_unknown_feature() {
+        let result = apply_feature_ops(
+            sample_dependency_features(),
+            &["bogus".to_string()],
+            &[],
+            false,
+        );
+
+        assert!(result.is_err());
+    }
+}